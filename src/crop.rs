@@ -0,0 +1,398 @@
+//! Whitespace-margin cropping.
+//!
+//! Detects each page's inked content area via Ghostscript's `bbox` output
+//! device, then re-renders the document through `pdfwrite` with a generated
+//! PostScript prologue that shrinks the `/MediaBox` down to that content box
+//! (plus an optional margin). Adapted from the pdfcrop-style cropping the
+//! epspdf utility relies on.
+
+use crate::{Error, Result};
+use std::fmt::Write as _;
+use std::path::Path;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// ── BBox ──────────────────────────────────────────────────────────────────────
+
+/// A single page's content bounding box, in PDF points (1/72 inch).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BBox {
+    /// Lower-left x coordinate.
+    pub llx: f64,
+    /// Lower-left y coordinate.
+    pub lly: f64,
+    /// Upper-right x coordinate.
+    pub urx: f64,
+    /// Upper-right y coordinate.
+    pub ury: f64,
+}
+
+impl BBox {
+    /// Width of the box, in points.
+    #[must_use]
+    pub fn width(&self) -> f64 {
+        self.urx - self.llx
+    }
+
+    /// Height of the box, in points.
+    #[must_use]
+    pub fn height(&self) -> f64 {
+        self.ury - self.lly
+    }
+
+    /// Returns `true` when the box has zero or negative area, which
+    /// Ghostscript reports for blank pages.
+    #[must_use]
+    pub fn is_degenerate(&self) -> bool {
+        self.width() <= 0.0 || self.height() <= 0.0
+    }
+}
+
+// ── Detection ─────────────────────────────────────────────────────────────────
+
+/// Runs Ghostscript's `bbox` device over `input` and parses the
+/// `%%HiResBoundingBox: llx lly urx ury` line it writes to stderr for each
+/// page, in page order.
+///
+/// # Errors
+///
+/// - [`Error::Spawn`] — Ghostscript could not be launched.
+/// - [`Error::GhostscriptFailed`] — Ghostscript exited with a non-zero code.
+pub fn detect_bboxes(gs_bin: &str, input: &Path) -> Result<Vec<BBox>> {
+    let result = Command::new(gs_bin)
+        .arg("-sDEVICE=bbox")
+        .arg("-dNOPAUSE")
+        .arg("-dBATCH")
+        .arg("-dSAFER")
+        .arg(input)
+        .output()
+        .map_err(Error::Spawn)?;
+
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        return Err(Error::GhostscriptFailed(stderr.into_owned()));
+    }
+
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    Ok(stderr.lines().filter_map(parse_hires_bbox_line).collect())
+}
+
+/// Parses a single `%%HiResBoundingBox:` line into a [`BBox`].
+fn parse_hires_bbox_line(line: &str) -> Option<BBox> {
+    let rest = line.strip_prefix("%%HiResBoundingBox:")?;
+    let mut nums = rest.split_whitespace().filter_map(|s| s.parse::<f64>().ok());
+    Some(BBox {
+        llx: nums.next()?,
+        lly: nums.next()?,
+        urx: nums.next()?,
+        ury: nums.next()?,
+    })
+}
+
+/// A page's `/Rotate` value and its native (pre-rotation) `/MediaBox` size,
+/// both needed to map a detected content box back into the coordinate space
+/// the cropped `/PageSize` must be expressed in.
+#[derive(Copy, Clone, Debug)]
+struct PageGeometry {
+    /// Clockwise display rotation in degrees (`0`, `90`, `180`, or `270`).
+    rotate: i32,
+    /// Native `/MediaBox` width, in points.
+    width: f64,
+    /// Native `/MediaBox` height, in points.
+    height: f64,
+}
+
+/// Reads each page's `/Rotate` value (defaulting to `0`) and native
+/// `/MediaBox` width/height by driving Ghostscript's PDF interpreter
+/// directly (`runpdfbegin`/`pdfgetpage`) without rendering anything.
+fn page_geometries(gs_bin: &str, input: &Path, page_count: usize) -> Result<Vec<PageGeometry>> {
+    if page_count == 0 {
+        return Ok(Vec::new());
+    }
+
+    let path = input.display().to_string().replace('\\', "\\\\");
+    let script = format!(
+        "({path}) (r) file runpdfbegin \
+         1 1 {page_count} {{ \
+           /PDFPAGE exch pdfgetpage def \
+           PDFPAGE /MediaBox get aload pop \
+           /PDFURY exch def /PDFURX exch def /PDFLLY exch def /PDFLLX exch def \
+           PDFPAGE /Rotate known {{ PDFPAGE /Rotate get }} {{ 0 }} ifelse =only \
+           ( ) print PDFURX PDFLLX sub =only \
+           ( ) print PDFURY PDFLLY sub == \
+         }} for \
+         pdfclose"
+    );
+
+    let result = Command::new(gs_bin)
+        .arg("-dNODISPLAY")
+        .arg("-dNOPAUSE")
+        .arg("-dBATCH")
+        .arg("-dSAFER")
+        .arg("-q")
+        .arg("-c")
+        .arg(script)
+        .output()
+        .map_err(Error::Spawn)?;
+
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        return Err(Error::GhostscriptFailed(stderr.into_owned()));
+    }
+
+    let stdout = String::from_utf8_lossy(&result.stdout);
+    Ok(stdout.lines().filter_map(parse_geometry_line).collect())
+}
+
+/// Parses a `"<rotate> <width> <height>"` line emitted by [`page_geometries`].
+fn parse_geometry_line(line: &str) -> Option<PageGeometry> {
+    let mut nums = line.split_whitespace();
+    Some(PageGeometry {
+        rotate: nums.next()?.parse().ok()?,
+        width: nums.next()?.parse().ok()?,
+        height: nums.next()?.parse().ok()?,
+    })
+}
+
+// ── Cropping ──────────────────────────────────────────────────────────────────
+
+/// Crops `input` to each page's detected content box (expanded by `margin`
+/// points on every side) and writes the result to `output`.
+///
+/// Pages whose detected box is degenerate (zero or negative area) keep their
+/// original `MediaBox` untouched. Rotated pages are handled by mapping the
+/// detected (display-space) box back into the page's native `MediaBox`
+/// coordinate space; for a `/Rotate` of `90` or `270` that mapping exchanges
+/// the box's width and height.
+///
+/// # Errors
+///
+/// - [`Error::Spawn`] — Ghostscript could not be launched.
+/// - [`Error::GhostscriptFailed`] — Ghostscript exited with a non-zero code.
+/// - [`Error::Io`] — the generated PostScript prologue could not be written.
+pub fn crop_pdf(gs_bin: &str, input: &Path, output: &Path, margin: f64) -> Result<()> {
+    let bboxes = detect_bboxes(gs_bin, input)?;
+    let geometries = page_geometries(gs_bin, input, bboxes.len())?;
+    let prologue = build_prologue(&bboxes, &geometries, margin);
+
+    // Keyed by pid *and* a per-process counter: with #chunk0-4's `--jobs`,
+    // several threads in the same process can be cropping concurrently, so
+    // the pid alone is not enough to keep their prologues from colliding.
+    static PROLOGUE_SEQ: AtomicUsize = AtomicUsize::new(0);
+    let seq = PROLOGUE_SEQ.fetch_add(1, Ordering::SeqCst);
+
+    let mut prologue_path = std::env::temp_dir();
+    prologue_path.push(format!(
+        "pdf-size-shrinker-crop-{}-{seq}.ps",
+        std::process::id()
+    ));
+    std::fs::write(&prologue_path, prologue).map_err(|source| Error::Io {
+        path: prologue_path.clone(),
+        source,
+    })?;
+
+    let result = Command::new(gs_bin)
+        .arg("-sDEVICE=pdfwrite")
+        .arg("-dCompatibilityLevel=1.4")
+        .arg("-dNOPAUSE")
+        .arg("-dBATCH")
+        .arg("-dSAFER")
+        .arg(format!("-sOutputFile={}", output.display()))
+        .arg(&prologue_path)
+        .arg(input)
+        .output();
+
+    let _ = std::fs::remove_file(&prologue_path);
+    let result = result.map_err(Error::Spawn)?;
+
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        return Err(Error::GhostscriptFailed(stderr.into_owned()));
+    }
+
+    Ok(())
+}
+
+/// Maps a detected content-box corner `(x, y)`, reported by the `bbox`
+/// device in display (post-rotation) space, back into the page's native
+/// `MediaBox` space — the space `/PageSize` and `setpagedevice` operate in —
+/// given the native page's `width`/`height` and its `/Rotate` value.
+///
+/// Assumes the native `MediaBox` origin is `(0, 0)`, true for the vast
+/// majority of PDFs; pages with an offset `MediaBox` origin are cropped
+/// slightly off from the true content box.
+fn to_native_space(x: f64, y: f64, rotate: i32, width: f64, height: f64) -> (f64, f64) {
+    match rotate {
+        90 => (width - y, x),
+        180 => (width - x, height - y),
+        270 => (y, height - x),
+        _ => (x, y),
+    }
+}
+
+/// Builds a PostScript prologue that hooks the page device's `/BeginPage`
+/// procedure to set `/PageSize` to each page's (margin-expanded) content box
+/// and translate the origin so only that box survives in the `pdfwrite`
+/// output — one procedure per page, looked up by a page counter the hook
+/// increments itself, mirroring the per-page `BeginPage` indexing pdfcrop
+/// uses.
+fn build_prologue(bboxes: &[BBox], geometries: &[PageGeometry], margin: f64) -> String {
+    let mut ps = String::from("%!\n/CropProcs [\n");
+
+    for (i, bbox) in bboxes.iter().enumerate() {
+        if bbox.is_degenerate() {
+            // Degenerate content box (blank page): fall back to the
+            // original MediaBox by leaving the page device untouched.
+            ps.push_str("  {}\n");
+            continue;
+        }
+
+        let geom = geometries.get(i).copied().unwrap_or(PageGeometry {
+            rotate: 0,
+            width: bbox.width(),
+            height: bbox.height(),
+        });
+        let rotate = geom.rotate.rem_euclid(360);
+
+        let (x0, y0) = to_native_space(bbox.llx, bbox.lly, rotate, geom.width, geom.height);
+        let (x1, y1) = to_native_space(bbox.urx, bbox.ury, rotate, geom.width, geom.height);
+        let (llx, urx) = (x0.min(x1), x0.max(x1));
+        let (lly, ury) = (y0.min(y1), y0.max(y1));
+
+        let w = (urx - llx) + 2.0 * margin;
+        let h = (ury - lly) + 2.0 * margin;
+        let llx = llx - margin;
+        let lly = lly - margin;
+
+        let _ = writeln!(
+            ps,
+            "  {{ << /PageSize [{w} {h}] >> setpagedevice {llx} neg {lly} neg translate }}"
+        );
+    }
+
+    ps.push_str(
+        "] def\n\
+         /CropPageIndex 0 def\n\
+         << /BeginPage {\n\
+         \u{20}\u{20}CropPageIndex CropProcs length lt { CropProcs CropPageIndex get exec } if\n\
+         \u{20}\u{20}/CropPageIndex CropPageIndex 1 add def\n\
+         } >> setpagedevice\n",
+    );
+
+    ps
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+// Exact float comparisons are fine here: these values are small integers or
+// exact binary fractions, so parsing and subtracting them is exact.
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hires_bbox_line() {
+        let bbox = parse_hires_bbox_line("%%HiResBoundingBox: 10.5 20 585.0 770.25").unwrap();
+        assert_eq!(bbox.llx, 10.5);
+        assert_eq!(bbox.lly, 20.0);
+        assert_eq!(bbox.urx, 585.0);
+        assert_eq!(bbox.ury, 770.25);
+    }
+
+    #[test]
+    fn ignores_unrelated_lines() {
+        assert!(parse_hires_bbox_line("%%BoundingBox: 0 0 612 792").is_none());
+        assert!(parse_hires_bbox_line("some other stderr noise").is_none());
+    }
+
+    #[test]
+    fn bbox_dimensions_and_degeneracy() {
+        let bbox = BBox {
+            llx: 10.0,
+            lly: 10.0,
+            urx: 110.0,
+            ury: 60.0,
+        };
+        assert_eq!(bbox.width(), 100.0);
+        assert_eq!(bbox.height(), 50.0);
+        assert!(!bbox.is_degenerate());
+
+        let empty = BBox {
+            llx: 10.0,
+            lly: 10.0,
+            urx: 10.0,
+            ury: 10.0,
+        };
+        assert!(empty.is_degenerate());
+    }
+
+    #[test]
+    fn to_native_space_maps_rotated_corners() {
+        // A 600x800 native page; (100, 50) on-page becomes, after each
+        // clockwise display rotation, a different point in display space —
+        // to_native_space must invert that back to the original corner.
+        assert_eq!(to_native_space(100.0, 50.0, 0, 600.0, 800.0), (100.0, 50.0));
+        assert_eq!(to_native_space(50.0, 500.0, 90, 600.0, 800.0), (100.0, 50.0));
+        assert_eq!(
+            to_native_space(500.0, 750.0, 180, 600.0, 800.0),
+            (100.0, 50.0)
+        );
+        assert_eq!(
+            to_native_space(750.0, 100.0, 270, 600.0, 800.0),
+            (100.0, 50.0)
+        );
+    }
+
+    #[test]
+    fn build_prologue_indexes_one_proc_per_page() {
+        // Two distinct pages must each get their own crop box in the
+        // generated array, not a single box applied to every page.
+        let bboxes = vec![
+            BBox {
+                llx: 0.0,
+                lly: 0.0,
+                urx: 100.0,
+                ury: 100.0,
+            },
+            BBox {
+                llx: 10.0,
+                lly: 10.0,
+                urx: 50.0,
+                ury: 50.0,
+            },
+        ];
+        let geometries = vec![
+            PageGeometry {
+                rotate: 0,
+                width: 100.0,
+                height: 100.0,
+            },
+            PageGeometry {
+                rotate: 0,
+                width: 60.0,
+                height: 60.0,
+            },
+        ];
+        let ps = build_prologue(&bboxes, &geometries, 0.0);
+
+        assert_eq!(ps.matches("setpagedevice").count(), 3); // 2 crop boxes + BeginPage hook
+        assert!(ps.contains("[100 100]"));
+        assert!(ps.contains("[40 40]"));
+        assert!(ps.contains("/BeginPage"));
+        assert!(ps.contains("CropPageIndex CropProcs length lt"));
+    }
+
+    #[test]
+    fn build_prologue_skips_degenerate_pages() {
+        let bboxes = vec![BBox {
+            llx: 10.0,
+            lly: 10.0,
+            urx: 10.0,
+            ury: 10.0,
+        }];
+        let ps = build_prologue(&bboxes, &[], 0.0);
+        assert!(ps.contains("{}"));
+        assert!(!ps.contains("PageSize"));
+    }
+}
@@ -9,11 +9,18 @@
 //! # Quick start
 //!
 //! ```no_run
-//! use pdf_size_shrinker::{find_ghostscript, shrink_pdf, Quality, ShrinkOptions};
+//! use pdf_size_shrinker::{find_ghostscript, shrink_pdf, OutputFormat, Quality, ShrinkOptions};
 //! use std::path::Path;
 //!
 //! let gs = find_ghostscript().expect("Ghostscript not found");
-//! let opts = ShrinkOptions { gs_bin: &gs, quality: Quality::Ebook, verbose: false };
+//! let opts = ShrinkOptions {
+//!     gs_bin: &gs,
+//!     quality: Quality::Ebook,
+//!     output_format: OutputFormat::Pdf,
+//!     image_settings: None,
+//!     extra_args: &[],
+//!     verbose: false,
+//! };
 //! shrink_pdf(&opts, Path::new("input.pdf"), Path::new("output.pdf")).unwrap();
 //! ```
 
@@ -22,6 +29,9 @@ use std::process::Command;
 use thiserror::Error;
 use walkdir::WalkDir;
 
+mod crop;
+pub use crop::{crop_pdf, detect_bboxes, BBox};
+
 // ── Error ─────────────────────────────────────────────────────────────────────
 
 /// Errors that can occur during PDF collection or shrinking.
@@ -101,6 +111,73 @@ impl Quality {
     }
 }
 
+// ── OutputFormat ──────────────────────────────────────────────────────────────
+
+/// Output container format for [`shrink_pdf`], selecting the Ghostscript
+/// output device.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// PDF, via Ghostscript's `pdfwrite` device.
+    Pdf,
+    /// PostScript, via Ghostscript's `ps2write` device.
+    Ps,
+    /// Encapsulated PostScript, via Ghostscript's `eps2write` device.
+    Eps,
+}
+
+impl OutputFormat {
+    /// Returns the Ghostscript `-sDEVICE` value for this format.
+    #[must_use]
+    pub fn gs_device(self) -> &'static str {
+        match self {
+            Self::Pdf => "pdfwrite",
+            Self::Ps => "ps2write",
+            Self::Eps => "eps2write",
+        }
+    }
+
+    /// Returns the canonical file extension for this format, without a
+    /// leading dot.
+    #[must_use]
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Pdf => "pdf",
+            Self::Ps => "ps",
+            Self::Eps => "eps",
+        }
+    }
+
+    /// Detects the format implied by a file extension (case-insensitive,
+    /// without a leading dot), if recognized.
+    #[must_use]
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_ascii_lowercase().as_str() {
+            "pdf" => Some(Self::Pdf),
+            "ps" => Some(Self::Ps),
+            "eps" => Some(Self::Eps),
+            _ => None,
+        }
+    }
+}
+
+// ── ImageSettings ─────────────────────────────────────────────────────────────
+
+/// Fine-grained per-channel downsampling settings, overriding the
+/// [`Quality`] preset with the individual Ghostscript flags it hides.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ImageSettings {
+    /// Target resolution, in dpi, for color images.
+    pub color_dpi: u32,
+    /// Target resolution, in dpi, for grayscale images.
+    pub gray_dpi: u32,
+    /// Target resolution, in dpi, for monochrome (1-bit) images.
+    pub mono_dpi: u32,
+    /// Whether to downsample images at all (`-dDownsample*Images`).
+    pub downsample: bool,
+    /// JPEG recompression quality, from `1` (smallest) to `100` (best).
+    pub jpeg_quality: u8,
+}
+
 // ── ShrinkOptions ─────────────────────────────────────────────────────────────
 
 /// Configuration passed to [`shrink_pdf`].
@@ -110,6 +187,15 @@ pub struct ShrinkOptions<'a> {
     pub gs_bin: &'a str,
     /// Quality preset controlling the `-dPDFSETTINGS` Ghostscript flag.
     pub quality: Quality,
+    /// Output container format, selecting the Ghostscript output device.
+    pub output_format: OutputFormat,
+    /// When set, overrides `quality` with explicit per-channel downsampling
+    /// flags instead of the fixed `-dPDFSETTINGS` preset.
+    pub image_settings: Option<ImageSettings>,
+    /// Raw Ghostscript arguments appended verbatim just before `-sOutputFile`,
+    /// for options this crate doesn't otherwise model
+    /// (e.g. `-dFastWebView=true`).
+    pub extra_args: &'a [String],
     /// When `true`, Ghostscript's stdout is forwarded to the caller's stdout.
     pub verbose: bool,
 }
@@ -158,26 +244,49 @@ pub fn find_ghostscript() -> Option<String> {
 /// # Examples
 ///
 /// ```no_run
-/// use pdf_size_shrinker::{find_ghostscript, shrink_pdf, Quality, ShrinkOptions};
+/// use pdf_size_shrinker::{find_ghostscript, shrink_pdf, OutputFormat, Quality, ShrinkOptions};
 /// use std::path::Path;
 ///
 /// let gs = find_ghostscript().unwrap();
-/// let opts = ShrinkOptions { gs_bin: &gs, quality: Quality::Ebook, verbose: false };
+/// let opts = ShrinkOptions {
+///     gs_bin: &gs,
+///     quality: Quality::Ebook,
+///     output_format: OutputFormat::Pdf,
+///     image_settings: None,
+///     extra_args: &[],
+///     verbose: false,
+/// };
 /// shrink_pdf(&opts, Path::new("in.pdf"), Path::new("out.pdf")).unwrap();
 /// ```
 pub fn shrink_pdf(opts: &ShrinkOptions<'_>, input: &Path, output: &Path) -> Result<()> {
     let mut cmd = Command::new(opts.gs_bin);
-    cmd.arg("-sDEVICE=pdfwrite")
+    cmd.arg(format!("-sDEVICE={}", opts.output_format.gs_device()))
         .arg("-dCompatibilityLevel=1.4")
-        .arg(format!("-dPDFSETTINGS={}", opts.quality.gs_setting()))
         .arg("-dNOPAUSE")
         .arg("-dBATCH")
         .arg("-dSAFER");
 
+    if let Some(img) = opts.image_settings {
+        cmd.arg(format!("-dDownsampleColorImages={}", img.downsample))
+            .arg(format!("-dColorImageResolution={}", img.color_dpi))
+            .arg("-dColorImageDownsampleType=/Bicubic")
+            .arg(format!("-dDownsampleGrayImages={}", img.downsample))
+            .arg(format!("-dGrayImageResolution={}", img.gray_dpi))
+            .arg("-dGrayImageDownsampleType=/Bicubic")
+            .arg(format!("-dDownsampleMonoImages={}", img.downsample))
+            .arg(format!("-dMonoImageResolution={}", img.mono_dpi))
+            .arg("-dMonoImageDownsampleType=/Bicubic")
+            .arg(format!("-dJPEGQ={}", img.jpeg_quality));
+    } else {
+        cmd.arg(format!("-dPDFSETTINGS={}", opts.quality.gs_setting()));
+    }
+
     if !opts.verbose {
         cmd.arg("-dQUIET");
     }
 
+    cmd.args(opts.extra_args);
+
     cmd.arg(format!("-sOutputFile={}", output.display()))
         .arg(input);
 
@@ -195,11 +304,11 @@ pub fn shrink_pdf(opts: &ShrinkOptions<'_>, input: &Path, output: &Path) -> Resu
     Ok(())
 }
 
-/// Computes the output path for a shrunk PDF.
+/// Computes the output path for a shrunk file.
 ///
 /// When `output_dir` is `Some`, the file is placed in that directory.
 /// Otherwise the compressed file is placed alongside `input`.
-/// The filename is `<original-stem><suffix>.pdf`.
+/// The filename is `<original-stem><suffix>.<ext>`.
 ///
 /// # Examples
 ///
@@ -208,48 +317,49 @@ pub fn shrink_pdf(opts: &ShrinkOptions<'_>, input: &Path, output: &Path) -> Resu
 /// use pdf_size_shrinker::output_path;
 ///
 /// // Default: same directory as input.
-/// let p = output_path(Path::new("/docs/report.pdf"), "_compressed", None);
+/// let p = output_path(Path::new("/docs/report.pdf"), "_compressed", "pdf", None);
 /// assert_eq!(p, Path::new("/docs/report_compressed.pdf"));
 ///
 /// // Custom output directory.
-/// let p = output_path(Path::new("/docs/report.pdf"), "_compressed", Some(Path::new("/out")));
+/// let p = output_path(Path::new("/docs/report.pdf"), "_compressed", "pdf", Some(Path::new("/out")));
 /// assert_eq!(p, Path::new("/out/report_compressed.pdf"));
 /// ```
 #[must_use]
-pub fn output_path(input: &Path, suffix: &str, output_dir: Option<&Path>) -> PathBuf {
+pub fn output_path(input: &Path, suffix: &str, ext: &str, output_dir: Option<&Path>) -> PathBuf {
     let stem = input.file_stem().unwrap_or_default().to_string_lossy();
-    let name = format!("{stem}{suffix}.pdf");
+    let name = format!("{stem}{suffix}.{ext}");
     output_dir.map_or_else(|| input.with_file_name(&name), |dir| dir.join(&name))
 }
 
-/// Collects PDF files from a mixed list of file and directory paths.
+/// Collects supported input files (`.pdf`, `.ps`, `.eps`) from a mixed list
+/// of file and directory paths.
 ///
-/// - Plain files that do not end in `.pdf` (case-insensitive) are skipped with
-///   a warning printed to stderr.
+/// - Plain files whose extension isn't recognized are skipped with a
+///   warning printed to stderr.
 /// - Directories are walked one level deep unless `recursive` is `true`.
 /// - Paths that do not exist produce a warning on stderr and are skipped.
 ///
-/// Returns every matched PDF path in the order they were encountered.
+/// Returns every matched path in the order they were encountered.
 ///
 /// # Examples
 ///
 /// ```no_run
 /// use std::path::PathBuf;
-/// use pdf_size_shrinker::collect_pdfs;
+/// use pdf_size_shrinker::collect_inputs;
 ///
-/// let pdfs = collect_pdfs(&[PathBuf::from("./invoices")], true);
-/// println!("found {} PDFs", pdfs.len());
+/// let inputs = collect_inputs(&[PathBuf::from("./invoices")], true);
+/// println!("found {} files", inputs.len());
 /// ```
 #[must_use]
-pub fn collect_pdfs(inputs: &[PathBuf], recursive: bool) -> Vec<PathBuf> {
+pub fn collect_inputs(inputs: &[PathBuf], recursive: bool) -> Vec<PathBuf> {
     let mut files = Vec::new();
 
     for input in inputs {
         if input.is_file() {
-            if is_pdf(input) {
+            if is_supported_input(input) {
                 files.push(input.clone());
             } else {
-                eprintln!("warn: skipping non-PDF file: {}", input.display());
+                eprintln!("warn: skipping unsupported file: {}", input.display());
             }
         } else if input.is_dir() {
             let max_depth = if recursive { usize::MAX } else { 1 };
@@ -259,7 +369,7 @@ pub fn collect_pdfs(inputs: &[PathBuf], recursive: bool) -> Vec<PathBuf> {
                 .filter_map(std::result::Result::ok)
             {
                 let path = entry.into_path();
-                if path.is_file() && is_pdf(&path) {
+                if path.is_file() && is_supported_input(&path) {
                     files.push(path);
                 }
             }
@@ -273,11 +383,13 @@ pub fn collect_pdfs(inputs: &[PathBuf], recursive: bool) -> Vec<PathBuf> {
 
 // ── Private helpers ───────────────────────────────────────────────────────────
 
-/// Returns `true` if `path` has a `.pdf` extension (case-insensitive).
+/// Returns `true` if `path` has a recognized input extension
+/// (`.pdf`, `.ps`, or `.eps`, case-insensitive).
 #[inline]
-fn is_pdf(path: &Path) -> bool {
+fn is_supported_input(path: &Path) -> bool {
     path.extension()
-        .is_some_and(|ext| ext.eq_ignore_ascii_case("pdf"))
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| OutputFormat::from_extension(ext).is_some())
 }
 
 // ── Tests ─────────────────────────────────────────────────────────────────────
@@ -296,11 +408,28 @@ mod tests {
         assert_eq!(Quality::Prepress.gs_setting(), "/prepress");
     }
 
+    // OutputFormat ─────────────────────────────────────────────────────────────
+
+    #[test]
+    fn output_format_from_extension_is_case_insensitive() {
+        assert_eq!(OutputFormat::from_extension("PDF"), Some(OutputFormat::Pdf));
+        assert_eq!(OutputFormat::from_extension("ps"), Some(OutputFormat::Ps));
+        assert_eq!(OutputFormat::from_extension("Eps"), Some(OutputFormat::Eps));
+        assert_eq!(OutputFormat::from_extension("docx"), None);
+    }
+
+    #[test]
+    fn output_format_gs_devices_are_correct() {
+        assert_eq!(OutputFormat::Pdf.gs_device(), "pdfwrite");
+        assert_eq!(OutputFormat::Ps.gs_device(), "ps2write");
+        assert_eq!(OutputFormat::Eps.gs_device(), "eps2write");
+    }
+
     // output_path ──────────────────────────────────────────────────────────────
 
     #[test]
     fn output_path_same_directory() {
-        let result = output_path(Path::new("/docs/report.pdf"), "_compressed", None);
+        let result = output_path(Path::new("/docs/report.pdf"), "_compressed", "pdf", None);
         assert_eq!(result, Path::new("/docs/report_compressed.pdf"));
     }
 
@@ -309,6 +438,7 @@ mod tests {
         let result = output_path(
             Path::new("/docs/report.pdf"),
             "_compressed",
+            "pdf",
             Some(Path::new("/out")),
         );
         assert_eq!(result, Path::new("/out/report_compressed.pdf"));
@@ -316,56 +446,55 @@ mod tests {
 
     #[test]
     fn output_path_custom_suffix() {
-        let result = output_path(Path::new("/a/b.pdf"), "_min", None);
+        let result = output_path(Path::new("/a/b.pdf"), "_min", "pdf", None);
         assert_eq!(result, Path::new("/a/b_min.pdf"));
     }
 
     #[test]
     fn output_path_preserves_stem_with_dots() {
-        let result = output_path(Path::new("/a/report.v2.pdf"), "_small", None);
+        let result = output_path(Path::new("/a/report.v2.pdf"), "_small", "pdf", None);
         assert_eq!(result, Path::new("/a/report.v2_small.pdf"));
     }
 
-    // is_pdf ───────────────────────────────────────────────────────────────────
-
     #[test]
-    fn is_pdf_matches_lowercase_extension() {
-        assert!(is_pdf(Path::new("file.pdf")));
+    fn output_path_uses_target_extension() {
+        let result = output_path(Path::new("/a/report.eps"), "_small", "pdf", None);
+        assert_eq!(result, Path::new("/a/report_small.pdf"));
     }
 
-    #[test]
-    fn is_pdf_matches_uppercase_extension() {
-        assert!(is_pdf(Path::new("file.PDF")));
-    }
+    // is_supported_input ───────────────────────────────────────────────────────
 
     #[test]
-    fn is_pdf_matches_mixed_case_extension() {
-        assert!(is_pdf(Path::new("file.Pdf")));
+    fn is_supported_input_matches_pdf_ps_eps() {
+        assert!(is_supported_input(Path::new("file.pdf")));
+        assert!(is_supported_input(Path::new("file.PDF")));
+        assert!(is_supported_input(Path::new("file.ps")));
+        assert!(is_supported_input(Path::new("file.eps")));
     }
 
     #[test]
-    fn is_pdf_rejects_other_extensions() {
-        assert!(!is_pdf(Path::new("file.docx")));
-        assert!(!is_pdf(Path::new("file.txt")));
+    fn is_supported_input_rejects_other_extensions() {
+        assert!(!is_supported_input(Path::new("file.docx")));
+        assert!(!is_supported_input(Path::new("file.txt")));
     }
 
     #[test]
-    fn is_pdf_rejects_no_extension() {
-        assert!(!is_pdf(Path::new("file")));
+    fn is_supported_input_rejects_no_extension() {
+        assert!(!is_supported_input(Path::new("file")));
     }
 
-    // collect_pdfs ─────────────────────────────────────────────────────────────
+    // collect_inputs ───────────────────────────────────────────────────────────
 
     #[test]
-    fn collect_pdfs_skips_nonexistent_paths() {
-        let result = collect_pdfs(&[PathBuf::from("/nonexistent/ghost.pdf")], false);
+    fn collect_inputs_skips_nonexistent_paths() {
+        let result = collect_inputs(&[PathBuf::from("/nonexistent/ghost.pdf")], false);
         assert!(result.is_empty());
     }
 
     #[test]
-    fn collect_pdfs_skips_non_pdf_files() {
-        // Use this source file which definitely exists but is not a PDF.
-        let result = collect_pdfs(&[PathBuf::from(file!())], false);
+    fn collect_inputs_skips_unsupported_files() {
+        // Use this source file which definitely exists but isn't a supported input.
+        let result = collect_inputs(&[PathBuf::from(file!())], false);
         assert!(result.is_empty());
     }
 }
@@ -6,10 +6,14 @@ use anyhow::{Context as _, bail};
 use clap::{Parser, ValueEnum};
 use colored::Colorize;
 use human_bytes::human_bytes;
-use pdf_size_shrinker::{Error as PdfError, Quality};
-use pdf_size_shrinker::{ShrinkOptions, collect_pdfs, find_ghostscript, output_path, shrink_pdf};
+use pdf_size_shrinker::{Error as PdfError, ImageSettings, OutputFormat, Quality};
+use pdf_size_shrinker::{
+    ShrinkOptions, collect_inputs, crop_pdf, find_ghostscript, output_path, shrink_pdf,
+};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 // ── CLI types ─────────────────────────────────────────────────────────────────
 
@@ -37,11 +41,34 @@ impl From<QualityArg> for Quality {
     }
 }
 
-/// Compress and reduce PDF file sizes using Ghostscript.
+/// CLI mirror of [`OutputFormat`] that derives [`ValueEnum`] for clap integration.
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum ToArg {
+    /// Portable Document Format
+    Pdf,
+    /// PostScript
+    Ps,
+    /// Encapsulated PostScript
+    Eps,
+}
+
+impl From<ToArg> for OutputFormat {
+    fn from(to: ToArg) -> Self {
+        match to {
+            ToArg::Pdf => Self::Pdf,
+            ToArg::Ps => Self::Ps,
+            ToArg::Eps => Self::Eps,
+        }
+    }
+}
+
+/// Compress and reduce PDF, PostScript, and EPS file sizes using Ghostscript.
 #[derive(Parser, Debug)]
 #[command(name = "pdfshrinker", version, about, long_about = None)]
+// Each flag is an independent, user-facing CLI switch, not internal state.
+#[allow(clippy::struct_excessive_bools)]
 struct Cli {
-    /// Input PDF file(s) or directory
+    /// Input PDF/PostScript/EPS file(s) or directory
     #[arg(required = true, value_name = "INPUT")]
     inputs: Vec<PathBuf>,
 
@@ -57,6 +84,44 @@ struct Cli {
     #[arg(short, long, value_enum, default_value_t = QualityArg::Ebook)]
     quality: QualityArg,
 
+    /// Output format; defaults to matching each input's extension
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    to: Option<ToArg>,
+
+    /// Resolution (dpi) for color images; overrides --quality
+    #[arg(long, value_name = "DPI")]
+    color_dpi: Option<u32>,
+
+    /// Resolution (dpi) for grayscale images; overrides --quality
+    #[arg(long, value_name = "DPI")]
+    gray_dpi: Option<u32>,
+
+    /// Resolution (dpi) for monochrome images; overrides --quality
+    #[arg(long, value_name = "DPI")]
+    mono_dpi: Option<u32>,
+
+    /// Disable image downsampling; overrides --quality
+    #[arg(long)]
+    no_downsample: bool,
+
+    /// JPEG recompression quality (1-100); overrides --quality
+    #[arg(long, value_name = "QUALITY", value_parser = clap::value_parser!(u8).range(1..=100))]
+    jpeg_quality: Option<u8>,
+
+    /// Crop empty margins from page content before recompressing, with an
+    /// optional margin in points (e.g. `--crop=6`)
+    #[arg(long, value_name = "MARGIN", num_args = 0..=1, default_missing_value = "0")]
+    crop: Option<f64>,
+
+    /// Number of files to process concurrently [default: number of CPUs]
+    #[arg(short, long, value_name = "N")]
+    jobs: Option<usize>,
+
+    /// Extra Ghostscript argument, forwarded verbatim (repeatable, e.g.
+    /// `--gs-arg -dFastWebView=true`)
+    #[arg(long = "gs-arg", value_name = "ARG", allow_hyphen_values = true)]
+    gs_arg: Vec<String>,
+
     /// Recursively process subdirectories
     #[arg(short, long)]
     recursive: bool,
@@ -79,101 +144,231 @@ fn main() {
     }
 }
 
-// ── Core runner ───────────────────────────────────────────────────────────────
-
-fn run() -> anyhow::Result<()> {
-    let cli = Cli::parse();
+// ── Per-file outcome ──────────────────────────────────────────────────────────
 
-    let gs = find_ghostscript().ok_or(PdfError::GhostscriptNotFound)?;
-
-    if let Some(ref dir) = cli.output_dir {
-        fs::create_dir_all(dir)
-            .with_context(|| format!("failed to create output directory '{}'", dir.display()))?;
-    }
+/// Result of shrinking a single file, buffered so output from concurrent
+/// workers can be printed in input order once every job has finished.
+struct FileOutcome {
+    /// Text for the `  shrinking ... done/skip/failed (...)` stdout line.
+    stdout: String,
+    /// Ghostscript error detail, if any, for stderr.
+    stderr: Option<String>,
+    /// Whether this file counts as a success or a failure in the summary.
+    success: bool,
+    /// Bytes saved (`0` if the file failed or wasn't smaller afterwards).
+    saved: u64,
+}
 
-    let pdfs = collect_pdfs(&cli.inputs, cli.recursive);
-    if pdfs.is_empty() {
-        bail!("no PDF files found");
-    }
+/// Crops (if requested) and shrinks a single input file.
+fn process_file(
+    pdf: &Path,
+    cli: &Cli,
+    gs: &str,
+    image_settings: Option<ImageSettings>,
+) -> FileOutcome {
+    let format = cli.to.map_or_else(
+        || {
+            pdf.extension()
+                .and_then(|ext| ext.to_str())
+                .and_then(OutputFormat::from_extension)
+                .unwrap_or(OutputFormat::Pdf)
+        },
+        Into::into,
+    );
 
     let opts = ShrinkOptions {
-        gs_bin: &gs,
+        gs_bin: gs,
         quality: cli.quality.into(),
+        output_format: format,
+        image_settings,
+        extra_args: &cli.gs_arg,
         verbose: cli.verbose,
     };
 
-    let mut success: usize = 0;
-    let mut failure: usize = 0;
-    let mut total_saved: u64 = 0;
+    let out = if cli.in_place {
+        pdf.with_extension(format!("_pdfshrinker_tmp.{}", format.extension()))
+    } else {
+        output_path(pdf, &cli.suffix, format.extension(), cli.output_dir.as_deref())
+    };
 
-    for pdf in &pdfs {
-        let out = if cli.in_place {
-            pdf.with_extension("_pdfshrinker_tmp.pdf")
-        } else {
-            output_path(pdf, &cli.suffix, cli.output_dir.as_deref())
-        };
+    let in_place_dest = pdf.with_extension(format.extension());
+    let dest_label = if cli.in_place {
+        in_place_dest.display().to_string()
+    } else {
+        out.display().to_string()
+    };
 
-        let dest_label = if cli.in_place {
-            pdf.display().to_string()
-        } else {
-            out.display().to_string()
-        };
-
-        print!(
-            "  {} {} \u{2192} {dest_label} ... ",
-            "shrinking".cyan().bold(),
-            pdf.display(),
-        );
-
-        let original_size = fs::metadata(pdf).map_or(0, |m| m.len());
-
-        match shrink_pdf(&opts, pdf, &out) {
-            Ok(()) => {
-                let new_size = fs::metadata(&out).map_or(0, |m| m.len());
-
-                if cli.in_place
-                    && let Err(e) = fs::rename(&out, pdf)
-                {
-                    eprintln!("{} rename failed: {e}", "error:".red().bold());
-                    let _ = fs::remove_file(&out);
-                    failure += 1;
-                    continue;
+    let header = format!(
+        "  {} {} \u{2192} {dest_label} ... ",
+        "shrinking".cyan().bold(),
+        pdf.display(),
+    );
+
+    let original_size = fs::metadata(pdf).map_or(0, |m| m.len());
+
+    if let Some(margin) = cli.crop {
+        let tmp = pdf.with_extension("_pdfshrinker_crop_tmp.pdf");
+        if let Err(e) = crop_pdf(gs, pdf, &tmp, margin) {
+            return FileOutcome {
+                stdout: format!("{header}{}\n", "failed".red().bold()),
+                stderr: Some(format!("  {e}")),
+                success: false,
+                saved: 0,
+            };
+        }
+        let outcome = shrink_and_finish(&opts, &tmp, &out, &in_place_dest, cli, original_size, &header);
+        let _ = fs::remove_file(&tmp);
+        outcome
+    } else {
+        shrink_and_finish(&opts, pdf, &out, &in_place_dest, cli, original_size, &header)
+    }
+}
+
+/// Runs [`shrink_pdf`] on `shrink_input` and builds the resulting [`FileOutcome`].
+fn shrink_and_finish(
+    opts: &ShrinkOptions<'_>,
+    shrink_input: &Path,
+    out: &Path,
+    in_place_dest: &Path,
+    cli: &Cli,
+    original_size: u64,
+    header: &str,
+) -> FileOutcome {
+    match shrink_pdf(opts, shrink_input, out) {
+        Ok(()) => {
+            let new_size = fs::metadata(out).map_or(0, |m| m.len());
+
+            if cli.in_place {
+                if let Err(e) = fs::rename(out, in_place_dest) {
+                    let _ = fs::remove_file(out);
+                    return FileOutcome {
+                        stdout: format!("{header}{}\n", "failed".red().bold()),
+                        stderr: Some(format!("{} rename failed: {e}", "error:".red().bold())),
+                        success: false,
+                        saved: 0,
+                    };
                 }
+            }
 
-                if new_size < original_size {
-                    let saved = original_size - new_size;
-                    total_saved = total_saved.saturating_add(saved);
-                    let pct = (saved as f64 / original_size as f64) * 100.0;
-                    println!(
-                        "{} ({} \u{2192} {}, saved {} / {:.1}%)",
+            if new_size < original_size {
+                let saved = original_size - new_size;
+                let pct = (saved as f64 / original_size as f64) * 100.0;
+                FileOutcome {
+                    stdout: format!(
+                        "{header}{} ({} \u{2192} {}, saved {} / {:.1}%)\n",
                         "done".green().bold(),
                         human_bytes(original_size as f64),
                         human_bytes(new_size as f64),
                         human_bytes(saved as f64),
                         pct,
-                    );
-                } else {
-                    // Output did not shrink — discard it.
-                    if !cli.in_place {
-                        let _ = fs::remove_file(&out);
-                    }
-                    println!(
-                        "{} ({} \u{2014} no reduction achieved; output discarded)",
+                    ),
+                    stderr: None,
+                    success: true,
+                    saved,
+                }
+            } else {
+                // Output did not shrink — discard it.
+                if !cli.in_place {
+                    let _ = fs::remove_file(out);
+                }
+                FileOutcome {
+                    stdout: format!(
+                        "{header}{} ({} \u{2014} no reduction achieved; output discarded)\n",
                         "skip".yellow().bold(),
                         human_bytes(original_size as f64),
-                    );
+                    ),
+                    stderr: None,
+                    success: true,
+                    saved: 0,
                 }
-
-                success += 1;
             }
-            Err(e) => {
-                println!("{}", "failed".red().bold());
-                eprintln!("  {e}");
-                let _ = fs::remove_file(&out);
-                failure += 1;
+        }
+        Err(e) => {
+            let _ = fs::remove_file(out);
+            FileOutcome {
+                stdout: format!("{header}{}\n", "failed".red().bold()),
+                stderr: Some(format!("  {e}")),
+                success: false,
+                saved: 0,
             }
         }
     }
+}
+
+// ── Core runner ───────────────────────────────────────────────────────────────
+
+fn run() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    let gs = find_ghostscript().ok_or(PdfError::GhostscriptNotFound)?;
+
+    if let Some(ref dir) = cli.output_dir {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create output directory '{}'", dir.display()))?;
+    }
+
+    let inputs = collect_inputs(&cli.inputs, cli.recursive);
+    if inputs.is_empty() {
+        bail!("no supported input files found");
+    }
+
+    let image_settings = (cli.color_dpi.is_some()
+        || cli.gray_dpi.is_some()
+        || cli.mono_dpi.is_some()
+        || cli.jpeg_quality.is_some()
+        || cli.no_downsample)
+        .then(|| ImageSettings {
+            color_dpi: cli.color_dpi.unwrap_or(150),
+            gray_dpi: cli.gray_dpi.unwrap_or(150),
+            mono_dpi: cli.mono_dpi.unwrap_or(300),
+            downsample: !cli.no_downsample,
+            jpeg_quality: cli.jpeg_quality.unwrap_or(90),
+        });
+
+    let jobs = cli
+        .jobs
+        .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, std::num::NonZero::get))
+        .clamp(1, inputs.len());
+
+    let results: Vec<Mutex<Option<FileOutcome>>> =
+        (0..inputs.len()).map(|_| Mutex::new(None)).collect();
+    let next = AtomicUsize::new(0);
+
+    std::thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::SeqCst);
+                let Some(pdf) = inputs.get(i) else {
+                    break;
+                };
+                let outcome = process_file(pdf, &cli, &gs, image_settings);
+                *results[i].lock().unwrap() = Some(outcome);
+            });
+        }
+    });
+
+    let mut success: usize = 0;
+    let mut failure: usize = 0;
+    let mut total_saved: u64 = 0;
+
+    for result in results {
+        let outcome = result
+            .into_inner()
+            .unwrap()
+            .expect("every input index is processed exactly once");
+
+        print!("{}", outcome.stdout);
+        if let Some(stderr) = &outcome.stderr {
+            eprintln!("{stderr}");
+        }
+
+        if outcome.success {
+            success += 1;
+            total_saved = total_saved.saturating_add(outcome.saved);
+        } else {
+            failure += 1;
+        }
+    }
 
     println!();
     println!(